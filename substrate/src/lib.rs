@@ -2,13 +2,72 @@
 
 #[ink::contract]
 mod simple_deployer {
+    use ink::env::call::{build_create, ExecutionInput, FromAccountId, Selector};
+    use ink::env::ContractEnv;
     use ink::prelude::string::String;
     use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
 
     #[ink(storage)]
     pub struct SimpleDeployer {
-        owner: AccountId,
+        owner: Option<AccountId>,
         function_count: u32,
+        deployed: Mapping<u32, AccountId>,
+        functions: Mapping<u32, FunctionRecord>,
+        latest_version: Mapping<String, u32>,
+        deployments_by: Mapping<AccountId, Vec<u32>>,
+    }
+
+    /// A minimal handle wrapping the `AccountId` of a contract instantiated
+    /// via `deploy_function`. Its sole purpose is to give `build_create` a
+    /// concrete contract reference type to decode the instantiation result
+    /// against; the deployer has no knowledge of the interface of the code
+    /// it instantiates, so this carries nothing beyond the address itself.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct DeployedContract {
+        account_id: AccountId,
+    }
+
+    impl ContractEnv for DeployedContract {
+        type Env = Environment;
+    }
+
+    impl FromAccountId<Environment> for DeployedContract {
+        fn from_account_id(account_id: AccountId) -> Self {
+            Self { account_id }
+        }
+    }
+
+    impl DeployedContract {
+        fn to_account_id(self) -> AccountId {
+            self.account_id
+        }
+    }
+
+    /// A registered, semantically-versioned function deployment, including
+    /// the provenance of who deployed it and when, and the condition under
+    /// which it becomes active.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct FunctionRecord {
+        name: String,
+        code_hash: Hash,
+        deployer: AccountId,
+        version: (u16, u16, u16),
+        block_number: u32,
+        timestamp: Timestamp,
+        trigger: TriggerType,
+        active: bool,
+    }
+
+    /// The condition under which a scheduled function becomes activatable.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum TriggerType {
+        /// Activatable as soon as the owner calls `activate`.
+        Manual,
+        /// Activatable once the chain has reached the given block number.
+        AfterBlock(u32),
     }
 
     #[ink(event)]
@@ -16,27 +75,163 @@ mod simple_deployer {
         #[ink(topic)]
         function_id: u32,
         name: String,
+        address: AccountId,
+        #[ink(topic)]
+        caller: AccountId,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct FunctionActivated {
+        #[ink(topic)]
+        function_id: u32,
+        name: String,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous: Option<AccountId>,
+        #[ink(topic)]
+        new: Option<AccountId>,
+    }
+
+    /// Errors that can occur while interacting with the deployer.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// The on-chain instantiation of the target contract failed, e.g. due
+        /// to a salt/address collision. Callers can retry with a new salt.
+        InstantiationFailed,
+        /// The caller is not the current owner.
+        NotOwner,
+        /// The proposed version is not strictly greater than the latest
+        /// registered version for this name.
+        StaleVersion,
+        /// No function record is registered under this id.
+        FunctionNotFound,
+        /// The function's trigger condition has not yet been met.
+        TriggerNotReady,
+        /// The function record is already active.
+        AlreadyActive,
     }
 
     impl SimpleDeployer {
         #[ink(constructor)]
         pub fn new() -> Self {
             Self {
-                owner: Self::env().caller(),
+                owner: Some(Self::env().caller()),
                 function_count: 0,
+                deployed: Mapping::default(),
+                functions: Mapping::default(),
+                latest_version: Mapping::default(),
+                deployments_by: Mapping::default(),
+            }
+        }
+
+        /// Returns `Ok(())` if the caller is the current owner, `Error::NotOwner`
+        /// otherwise. A renounced contract (`owner == None`) rejects everyone.
+        fn ensure_owner(&self) -> Result<(), Error> {
+            if self.owner == Some(self.env().caller()) {
+                Ok(())
+            } else {
+                Err(Error::NotOwner)
             }
         }
 
+        /// Transfers ownership of the contract to `new_owner`.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), Error> {
+            self.ensure_owner()?;
+            let previous = self.owner;
+            self.owner = Some(new_owner);
+            self.env().emit_event(OwnershipTransferred {
+                previous,
+                new: self.owner,
+            });
+            Ok(())
+        }
+
+        /// Renounces ownership, leaving the contract without an owner.
         #[ink(message)]
-        pub fn deploy_function(&mut self, name: String) -> u32 {
+        pub fn renounce_ownership(&mut self) -> Result<(), Error> {
+            self.ensure_owner()?;
+            let previous = self.owner;
+            self.owner = None;
+            self.env().emit_event(OwnershipTransferred {
+                previous,
+                new: None,
+            });
+            Ok(())
+        }
+
+        /// Instantiates a contract from `code_hash` on-chain and records the
+        /// resulting address against a newly assigned function id.
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn deploy_function(
+            &mut self,
+            name: String,
+            code_hash: Hash,
+            selector: [u8; 4],
+            endowment: Balance,
+            ref_time_limit: u64,
+            proof_size_limit: u64,
+            salt: Vec<u8>,
+        ) -> Result<AccountId, Error> {
+            self.ensure_owner()?;
+
+            let params = build_create::<DeployedContract>()
+                .code_hash(code_hash)
+                .ref_time_limit(ref_time_limit)
+                .proof_size_limit(proof_size_limit)
+                .endowment(endowment)
+                .exec_input(ExecutionInput::new(Selector::new(selector)))
+                .salt_bytes(salt)
+                .returns::<DeployedContract>()
+                .params();
+
+            let contract = self
+                .env()
+                .instantiate_contract(&params)
+                .map_err(|_| Error::InstantiationFailed)?
+                .map_err(|_| Error::InstantiationFailed)?;
+            let address = contract.to_account_id();
+
+            let caller = self.env().caller();
+            let timestamp = self.env().block_timestamp();
+
             self.function_count += 1;
+            let function_id = self.function_count;
+            self.deployed.insert(function_id, &address);
+            self.functions.insert(
+                function_id,
+                &FunctionRecord {
+                    name: name.clone(),
+                    code_hash,
+                    deployer: caller,
+                    version: (0, 0, 0),
+                    block_number: self.env().block_number(),
+                    timestamp,
+                    trigger: TriggerType::Manual,
+                    active: true,
+                },
+            );
+
+            let mut deployments = self.deployments_by.get(caller).unwrap_or_default();
+            deployments.push(function_id);
+            self.deployments_by.insert(caller, &deployments);
 
             self.env().emit_event(FunctionDeployed {
-                function_id: self.function_count,
+                function_id,
                 name,
+                address,
+                caller,
+                timestamp,
             });
 
-            self.function_count
+            Ok(address)
         }
 
         #[ink(message)]
@@ -45,7 +240,149 @@ mod simple_deployer {
         }
 
         #[ink(message)]
-        pub fn get_owner(&self) -> AccountId {
+        pub fn get_address(&self, function_id: u32) -> Option<AccountId> {
+            self.deployed.get(function_id)
+        }
+
+        /// Registers a new semantic version of `name`, rejecting it unless
+        /// `version` is strictly greater than the latest registered version
+        /// for that name.
+        #[ink(message)]
+        pub fn register_version(
+            &mut self,
+            name: String,
+            code_hash: Hash,
+            version: (u16, u16, u16),
+        ) -> Result<u32, Error> {
+            self.ensure_owner()?;
+
+            if let Some(latest_id) = self.latest_version.get(&name) {
+                // A missing record here would be an internal inconsistency;
+                // treat it as "no prior version" rather than reverting the
+                // whole message over it.
+                if let Some(latest) = self.functions.get(latest_id) {
+                    if version <= latest.version {
+                        return Err(Error::StaleVersion);
+                    }
+                }
+            }
+
+            self.function_count += 1;
+            let function_id = self.function_count;
+            let record = FunctionRecord {
+                name: name.clone(),
+                code_hash,
+                deployer: self.env().caller(),
+                version,
+                block_number: self.env().block_number(),
+                timestamp: self.env().block_timestamp(),
+                trigger: TriggerType::Manual,
+                active: true,
+            };
+            self.functions.insert(function_id, &record);
+            self.latest_version.insert(&name, &function_id);
+
+            Ok(function_id)
+        }
+
+        /// Looks up a single registered function record by id.
+        #[ink(message)]
+        pub fn get_function(&self, function_id: u32) -> Option<FunctionRecord> {
+            self.functions.get(function_id)
+        }
+
+        /// Pages through registered function records, starting at `start`
+        /// and returning at most `limit` entries.
+        #[ink(message)]
+        pub fn list_functions(&self, start: u32, limit: u32) -> Vec<FunctionRecord> {
+            (start..start.saturating_add(limit))
+                .filter_map(|id| self.functions.get(id))
+                .collect()
+        }
+
+        /// Registers a pending function under `trigger`, to be made live by
+        /// a later call to `activate`. The record starts out inactive.
+        #[ink(message)]
+        pub fn schedule_function(
+            &mut self,
+            name: String,
+            code_hash: Hash,
+            trigger: TriggerType,
+        ) -> Result<u32, Error> {
+            self.ensure_owner()?;
+
+            let caller = self.env().caller();
+            self.function_count += 1;
+            let function_id = self.function_count;
+            self.functions.insert(
+                function_id,
+                &FunctionRecord {
+                    name,
+                    code_hash,
+                    deployer: caller,
+                    version: (0, 0, 0),
+                    block_number: self.env().block_number(),
+                    timestamp: self.env().block_timestamp(),
+                    trigger,
+                    active: false,
+                },
+            );
+
+            Ok(function_id)
+        }
+
+        /// Activates a pending function once its trigger condition is met.
+        /// `TriggerType::Manual` is always ready; `TriggerType::AfterBlock(n)`
+        /// requires the current block number to be at least `n`.
+        #[ink(message)]
+        pub fn activate(&mut self, function_id: u32) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            let mut record = self
+                .functions
+                .get(function_id)
+                .ok_or(Error::FunctionNotFound)?;
+
+            if record.active {
+                return Err(Error::AlreadyActive);
+            }
+
+            match record.trigger {
+                TriggerType::Manual => {}
+                TriggerType::AfterBlock(block) => {
+                    if self.env().block_number() < block {
+                        return Err(Error::TriggerNotReady);
+                    }
+                }
+            }
+
+            record.active = true;
+            let name = record.name.clone();
+            self.functions.insert(function_id, &record);
+
+            self.env()
+                .emit_event(FunctionActivated { function_id, name });
+
+            Ok(())
+        }
+
+        /// Returns the `(deployer, block_number, timestamp)` provenance of a
+        /// registered function record.
+        #[ink(message)]
+        pub fn get_provenance(&self, function_id: u32) -> Option<(AccountId, u32, Timestamp)> {
+            self.functions
+                .get(function_id)
+                .map(|record| (record.deployer, record.block_number, record.timestamp))
+        }
+
+        /// Returns the ids of every function deployed by `account`.
+        #[ink(message)]
+        pub fn deployments_by(&self, account: AccountId) -> Vec<u32> {
+            self.deployments_by.get(account).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn get_owner(&self) -> Option<AccountId> {
             self.owner
         }
     }
@@ -55,11 +392,105 @@ mod simple_deployer {
         use super::*;
 
         #[ink::test]
-        fn deploy_function_works() {
+        fn get_function_count_starts_at_zero() {
+            let contract = SimpleDeployer::new();
+            assert_eq!(contract.get_function_count(), 0);
+        }
+
+        #[ink::test]
+        fn non_owner_cannot_deploy_function() {
+            let mut contract = SimpleDeployer::new();
+            let accounts = ink::env::test::default_accounts::<Environment>();
+            ink::env::test::set_caller::<Environment>(accounts.bob);
+            // The `ensure_owner` guard must reject the call before it ever
+            // reaches `instantiate_contract`, which the off-chain test
+            // engine does not support exercising.
+            assert_eq!(
+                contract.deploy_function(
+                    "scanner".to_string(),
+                    Hash::default(),
+                    [0u8; 4],
+                    0,
+                    0,
+                    0,
+                    Vec::new(),
+                ),
+                Err(Error::NotOwner)
+            );
+        }
+
+        #[ink::test]
+        fn non_owner_cannot_transfer_ownership() {
+            let mut contract = SimpleDeployer::new();
+            let accounts = ink::env::test::default_accounts::<Environment>();
+            ink::env::test::set_caller::<Environment>(accounts.bob);
+            assert_eq!(
+                contract.transfer_ownership(accounts.charlie),
+                Err(Error::NotOwner)
+            );
+        }
+
+        #[ink::test]
+        fn renounce_ownership_clears_owner() {
+            let mut contract = SimpleDeployer::new();
+            assert_eq!(contract.renounce_ownership(), Ok(()));
+            assert_eq!(contract.get_owner(), None);
+        }
+
+        #[ink::test]
+        fn register_version_rejects_non_increasing_versions() {
+            let mut contract = SimpleDeployer::new();
+            let code_hash = Hash::default();
+            let id = contract
+                .register_version("scanner".to_string(), code_hash, (1, 0, 0))
+                .unwrap();
+            assert_eq!(contract.get_function(id).unwrap().version, (1, 0, 0));
+
+            assert_eq!(
+                contract.register_version("scanner".to_string(), code_hash, (1, 0, 0)),
+                Err(Error::StaleVersion)
+            );
+
+            let next_id = contract
+                .register_version("scanner".to_string(), code_hash, (1, 1, 0))
+                .unwrap();
+            assert_eq!(contract.list_functions(id, 2).len(), 2);
+            assert_eq!(next_id, id + 1);
+        }
+
+        #[ink::test]
+        fn manual_trigger_activates_immediately() {
+            let mut contract = SimpleDeployer::new();
+            let id = contract
+                .schedule_function("scanner".to_string(), Hash::default(), TriggerType::Manual)
+                .unwrap();
+            assert!(!contract.get_function(id).unwrap().active);
+            assert_eq!(contract.activate(id), Ok(()));
+            assert!(contract.get_function(id).unwrap().active);
+        }
+
+        #[ink::test]
+        fn after_block_trigger_rejects_activation_too_early() {
+            let mut contract = SimpleDeployer::new();
+            let id = contract
+                .schedule_function(
+                    "scanner".to_string(),
+                    Hash::default(),
+                    TriggerType::AfterBlock(100),
+                )
+                .unwrap();
+            assert_eq!(contract.activate(id), Err(Error::TriggerNotReady));
+            assert!(!contract.get_function(id).unwrap().active);
+        }
+
+        #[ink::test]
+        fn activate_rejects_an_already_active_record() {
             let mut contract = SimpleDeployer::new();
-            let id = contract.deploy_function("test_function".to_string());
-            assert_eq!(id, 1);
-            assert_eq!(contract.get_function_count(), 1);
+            let id = contract
+                .register_version("scanner".to_string(), Hash::default(), (1, 0, 0))
+                .unwrap();
+            assert!(contract.get_function(id).unwrap().active);
+            assert_eq!(contract.activate(id), Err(Error::AlreadyActive));
         }
     }
 }